@@ -2,7 +2,7 @@
 
 use crate::{
     duration::Duration, fixed_point::FixedPoint, fraction::Fraction, instant::Instant,
-    time_int::TimeInt, timer::param, timer::Timer,
+    time_int::TimeInt, timeout::TimeoutError, timer::param, timer::Timer,
 };
 use core::{
     fmt::{self, Formatter},
@@ -60,6 +60,39 @@ pub trait Clock: Sized {
     {
         Timer::<param::None, param::None, Self, Dur>::new(&self, duration)
     }
+
+    /// Repeatedly call `op` until it completes or `duration` elapses
+    ///
+    /// `op` follows the non-blocking poll convention: `Ok(None)` means "not ready yet". This
+    /// arms a [`Timer`] for `duration` and calls `op` in a loop until it returns `Ok(Some(_))`,
+    /// returns `Err`, or the timer expires. This is the embedded analogue of an async `timeout`
+    /// for drivers that expose a non-blocking `poll` (e.g. checking a flag or reading a sensor),
+    /// sparing callers from hand-rolling an `is_expired()` loop themselves.
+    ///
+    /// # Errors
+    ///
+    /// - [`TimeoutError::TimedOut`] if `duration` elapses before `op` returns `Ok(Some(_))`
+    /// - [`TimeoutError::Other`] wrapping whatever error `op` itself returns
+    fn with_timeout<Dur, T, E>(
+        &self,
+        duration: Dur,
+        mut op: impl FnMut() -> Result<Option<T>, E>,
+    ) -> Result<T, TimeoutError<E>>
+    where
+        Dur: Duration + FixedPoint,
+    {
+        let timer = self.new_timer(duration).start().unwrap();
+        loop {
+            match op() {
+                Ok(Some(value)) => return Ok(value),
+                Ok(None) => {}
+                Err(error) => return Err(TimeoutError::Other(error)),
+            }
+            if timer.is_expired().unwrap() {
+                return Err(TimeoutError::TimedOut);
+            }
+        }
+    }
 }
 
 /// A duration unit type for specific [`Clock`](clock/trait.Clock.html)
@@ -97,3 +130,179 @@ impl<Clock: crate::Clock> fmt::Display for ClockDuration<Clock> {
         fmt::Display::fmt(&self.0, f)
     }
 }
+
+/// Rounding strategy used when converting a real-time quantity (milliseconds, etc.) into clock
+/// ticks
+///
+/// A `Clock`'s tick period rarely divides evenly into whole milliseconds/microseconds/nanoseconds,
+/// so a conversion from real time to ticks must decide what to do with the remainder.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Round down, discarding any fractional tick
+    ///
+    /// The resulting duration may be shorter than requested.
+    Floor,
+    /// Round up to the next whole tick
+    ///
+    /// Guarantees the resulting duration is never shorter than requested.
+    Ceil,
+}
+
+impl<Clock: crate::Clock> ClockDuration<Clock> {
+    /// Convert a millisecond quantity into clock ticks, rounding per `rounding`
+    ///
+    /// # Errors
+    ///
+    /// Returns `None` on overflow of `Clock::T`. See [`Self::from_millis_saturating`] for a
+    /// variant that clamps instead.
+    pub fn from_millis(ms: Clock::T, rounding: Rounding) -> Option<Self> {
+        Self::from_scaled(ms, Fraction::new(1, 1_000), rounding)
+    }
+
+    /// Like [`Self::from_millis`], but clamps to `Clock::T`'s maximum instead of overflowing
+    pub fn from_millis_saturating(ms: Clock::T, rounding: Rounding) -> Self {
+        Self::from_millis(ms, rounding).unwrap_or_else(|| Self::new(Clock::T::max_value()))
+    }
+
+    /// Convert a microsecond quantity into clock ticks, rounding per `rounding`
+    ///
+    /// # Errors
+    ///
+    /// Returns `None` on overflow of `Clock::T`. See [`Self::from_micros_saturating`] for a
+    /// variant that clamps instead.
+    pub fn from_micros(us: Clock::T, rounding: Rounding) -> Option<Self> {
+        Self::from_scaled(us, Fraction::new(1, 1_000_000), rounding)
+    }
+
+    /// Like [`Self::from_micros`], but clamps to `Clock::T`'s maximum instead of overflowing
+    pub fn from_micros_saturating(us: Clock::T, rounding: Rounding) -> Self {
+        Self::from_micros(us, rounding).unwrap_or_else(|| Self::new(Clock::T::max_value()))
+    }
+
+    /// Convert a nanosecond quantity into clock ticks, rounding per `rounding`
+    ///
+    /// # Errors
+    ///
+    /// Returns `None` on overflow of `Clock::T`. See [`Self::from_nanos_saturating`] for a
+    /// variant that clamps instead.
+    pub fn from_nanos(ns: Clock::T, rounding: Rounding) -> Option<Self> {
+        Self::from_scaled(ns, Fraction::new(1, 1_000_000_000), rounding)
+    }
+
+    /// Like [`Self::from_nanos`], but clamps to `Clock::T`'s maximum instead of overflowing
+    pub fn from_nanos_saturating(ns: Clock::T, rounding: Rounding) -> Self {
+        Self::from_nanos(ns, rounding).unwrap_or_else(|| Self::new(Clock::T::max_value()))
+    }
+
+    /// Convert this duration's ticks into whole milliseconds, rounding per `rounding`
+    pub fn as_millis(&self, rounding: Rounding) -> Clock::T {
+        Self::to_scaled(self.0, Fraction::new(1, 1_000), rounding)
+    }
+
+    /// Convert this duration's ticks into whole microseconds, rounding per `rounding`
+    pub fn as_micros(&self, rounding: Rounding) -> Clock::T {
+        Self::to_scaled(self.0, Fraction::new(1, 1_000_000), rounding)
+    }
+
+    /// Convert this duration's ticks into whole nanoseconds, rounding per `rounding`
+    pub fn as_nanos(&self, rounding: Rounding) -> Clock::T {
+        Self::to_scaled(self.0, Fraction::new(1, 1_000_000_000), rounding)
+    }
+
+    /// `value` is a quantity of `unit`-seconds (e.g. `unit = 1/1_000` for milliseconds);
+    /// convert it into whole `Clock::T` ticks
+    ///
+    /// Every multiplication, addition and subtraction on the way is checked: `None` is returned
+    /// the moment any of them would overflow `Clock::T`, rather than risking a silent wrap or a
+    /// panic deep inside rounding.
+    fn from_scaled(value: Clock::T, unit: Fraction, rounding: Rounding) -> Option<Self> {
+        let sf = Clock::SCALING_FACTOR;
+        let numerator = value
+            .checked_mul(&Clock::T::from(*unit.numerator()))?
+            .checked_mul(&Clock::T::from(*sf.denominator()))?;
+        let denominator = Clock::T::from(*unit.denominator())
+            .checked_mul(&Clock::T::from(*sf.numerator()))?;
+        Self::divide(numerator, denominator, rounding).map(Self::new)
+    }
+
+    /// The inverse of [`Self::from_scaled`]: convert `ticks` of `Clock::T` into a whole
+    /// quantity of `unit`-seconds, saturating to `Clock::T`'s maximum on overflow
+    fn to_scaled(ticks: Clock::T, unit: Fraction, rounding: Rounding) -> Clock::T {
+        Self::to_scaled_checked(ticks, unit, rounding).unwrap_or_else(Clock::T::max_value)
+    }
+
+    fn to_scaled_checked(ticks: Clock::T, unit: Fraction, rounding: Rounding) -> Option<Clock::T> {
+        let sf = Clock::SCALING_FACTOR;
+        let numerator = ticks
+            .checked_mul(&Clock::T::from(*sf.numerator()))?
+            .checked_mul(&Clock::T::from(*unit.denominator()))?;
+        let denominator =
+            Clock::T::from(*sf.denominator()).checked_mul(&Clock::T::from(*unit.numerator()))?;
+        Self::divide(numerator, denominator, rounding)
+    }
+
+    /// Divide `numerator` by `denominator`, rounding per `rounding`
+    ///
+    /// Returns `None` if rounding up would overflow `Clock::T`, rather than wrapping or
+    /// panicking.
+    fn divide(numerator: Clock::T, denominator: Clock::T, rounding: Rounding) -> Option<Clock::T> {
+        match rounding {
+            Rounding::Floor => Some(numerator / denominator),
+            Rounding::Ceil => {
+                let adjustment = denominator.checked_sub(&1.into())?;
+                let numerator = numerator.checked_add(&adjustment)?;
+                Some(numerator / denominator)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestClock;
+
+    impl crate::Clock for TestClock {
+        type T = u32;
+        // 1 tick = 1/1_500 s, so 1 ms doesn't divide evenly into whole ticks (1.5 ticks).
+        const SCALING_FACTOR: Fraction = Fraction::new(1, 1_500);
+
+        fn try_now(&self) -> Result<Instant<Self>, Error> {
+            Err(Error::NotRunning)
+        }
+    }
+
+    #[test]
+    fn ceil_rounds_up_and_floor_rounds_down() {
+        let floor = ClockDuration::<TestClock>::from_millis(1, Rounding::Floor).unwrap();
+        let ceil = ClockDuration::<TestClock>::from_millis(1, Rounding::Ceil).unwrap();
+        assert_eq!(floor.0, 1);
+        assert_eq!(ceil.0, 2);
+    }
+
+    #[test]
+    fn millis_round_trip_when_evenly_divisible() {
+        let duration = ClockDuration::<TestClock>::from_millis(2, Rounding::Floor).unwrap();
+        assert_eq!(duration.0, 3);
+        assert_eq!(duration.as_millis(Rounding::Floor), 2);
+        assert_eq!(duration.as_millis(Rounding::Ceil), 2);
+    }
+
+    #[test]
+    fn from_millis_returns_none_on_overflow() {
+        assert!(ClockDuration::<TestClock>::from_millis(u32::MAX, Rounding::Ceil).is_none());
+    }
+
+    #[test]
+    fn from_millis_saturating_clamps_instead_of_overflowing() {
+        let duration = ClockDuration::<TestClock>::from_millis_saturating(u32::MAX, Rounding::Ceil);
+        assert_eq!(duration.0, u32::MAX);
+    }
+
+    #[test]
+    fn as_millis_saturates_rather_than_overflowing() {
+        let duration = ClockDuration::<TestClock>::new(u32::MAX);
+        assert_eq!(duration.as_millis(Rounding::Ceil), u32::MAX);
+    }
+}
@@ -0,0 +1,279 @@
+//! Async time driver abstraction, enabling [`Sleep`] futures on top of a [`Clock`]
+//!
+//! The blocking [`Delay`](crate::delay::Delay) busy-polls a wait closure. [`Driver`] lets a
+//! HAL instead park the executing task: it arms a deadline against a comparator/interrupt and
+//! the [`Sleep`] future resolves once that deadline has passed, without spinning the CPU.
+
+use crate::{clock::Clock, instant::Instant};
+use core::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// A [`Clock`] that can additionally arm a wakeup alarm for an async executor
+///
+/// Implementations back this with a hardware comparator interrupt: [`set_alarm`](Driver::set_alarm)
+/// arms the next interrupt and records `waker`; the interrupt handler calls [`Waker::wake`] once
+/// the deadline has passed, which causes the executor to poll the [`Sleep`] future again.
+pub trait Driver {
+    /// The `Clock` this driver is built on
+    type Clock: Clock;
+
+    /// Identifies a single outstanding alarm within this driver
+    ///
+    /// Returned by [`set_alarm`](Driver::set_alarm) and passed back in on the next call for the
+    /// same [`Sleep`], so an executor repolling a still-pending future (completely normal
+    /// behavior around `join!`/`select!`) updates its existing reservation in place instead of
+    /// consuming a new one each time.
+    type AlarmHandle: Copy;
+
+    /// The current instant, as observed by the underlying `Clock`
+    fn now(&self) -> Instant<Self::Clock>;
+
+    /// Arm an alarm for `at`, to be delivered to `waker`
+    ///
+    /// `handle` is `Some` when re-arming a [`Sleep`] that has already registered an alarm on a
+    /// previous poll; implementations must update that same reservation rather than taking a
+    /// fresh one. `handle` is `None` on a future's first poll, in which case a new reservation
+    /// is taken.
+    ///
+    /// Returns `None` if a new alarm cannot be armed (only reachable when `handle` is `None`
+    /// and the driver has no free alarm slots); the caller's future stays `Pending` and should
+    /// be polled again (e.g. on the next unrelated wakeup).
+    fn set_alarm(
+        &self,
+        handle: Option<Self::AlarmHandle>,
+        at: Instant<Self::Clock>,
+        waker: Waker,
+    ) -> Option<Self::AlarmHandle>;
+
+    /// Release a previously armed alarm, without waking it
+    ///
+    /// Called once a [`Sleep`] has resolved or been dropped, so its reservation can be reused
+    /// by a future `Sleep` instead of permanently consuming one of the driver's fixed alarm
+    /// slots.
+    fn clear_alarm(&self, handle: Self::AlarmHandle);
+}
+
+/// A `Future` that resolves once a [`Driver`]'s clock reaches a given [`Instant`]
+pub struct Sleep<'d, D: Driver> {
+    driver: &'d D,
+    at: Instant<D::Clock>,
+    handle: Cell<Option<D::AlarmHandle>>,
+}
+
+impl<'d, D: Driver> Sleep<'d, D> {
+    /// Sleep until the clock reaches `at`
+    pub fn until(driver: &'d D, at: Instant<D::Clock>) -> Self {
+        Self {
+            driver,
+            at,
+            handle: Cell::new(None),
+        }
+    }
+}
+
+impl<'d, D: Driver> Future for Sleep<'d, D> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.driver.now() >= self.at {
+            return Poll::Ready(());
+        }
+        let handle = self.driver.set_alarm(self.handle.get(), self.at, cx.waker().clone());
+        self.handle.set(handle);
+        Poll::Pending
+    }
+}
+
+impl<'d, D: Driver> Drop for Sleep<'d, D> {
+    /// Release this `Sleep`'s alarm reservation, if it ever registered one
+    ///
+    /// Without this, a `Sleep` that resolves or is cancelled (e.g. the losing branch of a
+    /// `select!`) after having been polled at least once would permanently hold its driver's
+    /// slot, eventually exhausting a fixed-capacity [`AlarmTable`].
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.get() {
+            self.driver.clear_alarm(handle);
+        }
+    }
+}
+
+/// A handle to a reserved slot within an [`AlarmTable`]
+///
+/// Passed back into [`AlarmTable::set`] to update that reservation in place.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AlarmHandle(usize);
+
+/// A fixed-capacity table of pending alarms, for `Driver` implementations to embed
+///
+/// Keeps `Driver::set_alarm` and the interrupt handler free of allocation: `set` records a
+/// waker against its deadline (reusing an existing [`AlarmHandle`]'s slot if given one, rather
+/// than always taking a fresh slot), `next_deadline` tells the interrupt handler when to next
+/// fire (so it can reprogram the comparator), and `fire_due` wakes and clears every alarm that
+/// has passed.
+pub struct AlarmTable<C: Clock, const CAP: usize> {
+    alarms: [Option<(Instant<C>, Waker)>; CAP],
+}
+
+impl<C: Clock, const CAP: usize> AlarmTable<C, CAP> {
+    const INIT: Option<(Instant<C>, Waker)> = None;
+
+    /// Construct an empty alarm table
+    pub fn new() -> Self {
+        Self {
+            alarms: [Self::INIT; CAP],
+        }
+    }
+
+    /// Record an alarm for `at`, to be delivered to `waker`
+    ///
+    /// If `handle` is `Some`, its existing slot is updated in place. Otherwise a free slot is
+    /// reserved and its handle returned; this returns `None`, leaving the table unchanged, if
+    /// every slot is already in use.
+    pub fn set(
+        &mut self,
+        handle: Option<AlarmHandle>,
+        at: Instant<C>,
+        waker: Waker,
+    ) -> Option<AlarmHandle> {
+        if let Some(AlarmHandle(index)) = handle {
+            self.alarms[index] = Some((at, waker));
+            return Some(AlarmHandle(index));
+        }
+        let index = self.alarms.iter().position(Option::is_none)?;
+        self.alarms[index] = Some((at, waker));
+        Some(AlarmHandle(index))
+    }
+
+    /// Release a reserved slot without waking it
+    ///
+    /// Callers should do this when a [`Sleep`] resolves or is dropped before firing, so its
+    /// slot can be reused.
+    pub fn clear(&mut self, handle: AlarmHandle) {
+        self.alarms[handle.0] = None;
+    }
+
+    /// The earliest armed deadline, if any
+    ///
+    /// `Driver` implementations use this to reprogram the hardware comparator after `set` or
+    /// `fire_due` changes the set of outstanding alarms.
+    pub fn next_deadline(&self) -> Option<Instant<C>> {
+        self.alarms.iter().flatten().map(|(at, _)| *at).min()
+    }
+
+    /// Wake and clear every alarm whose deadline is at or before `now`
+    ///
+    /// Intended to be called from the comparator interrupt handler.
+    pub fn fire_due(&mut self, now: Instant<C>) {
+        for slot in &mut self.alarms {
+            if matches!(slot, Some((at, _)) if *at <= now) {
+                if let Some((_, waker)) = slot.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock, fraction::Fraction};
+    use core::{
+        cell::RefCell,
+        task::{RawWaker, RawWakerVTable},
+    };
+
+    struct TestClock;
+
+    impl Clock for TestClock {
+        type T = u32;
+        const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000);
+
+        fn try_now(&self) -> Result<Instant<Self>, clock::Error> {
+            Err(clock::Error::NotRunning)
+        }
+    }
+
+    struct TestDriver {
+        now: Cell<Instant<TestClock>>,
+        alarms: RefCell<AlarmTable<TestClock, 1>>,
+    }
+
+    impl Driver for TestDriver {
+        type Clock = TestClock;
+        type AlarmHandle = AlarmHandle;
+
+        fn now(&self) -> Instant<Self::Clock> {
+            self.now.get()
+        }
+
+        fn set_alarm(
+            &self,
+            handle: Option<Self::AlarmHandle>,
+            at: Instant<Self::Clock>,
+            waker: Waker,
+        ) -> Option<Self::AlarmHandle> {
+            self.alarms.borrow_mut().set(handle, at, waker)
+        }
+
+        fn clear_alarm(&self, handle: Self::AlarmHandle) {
+            self.alarms.borrow_mut().clear(handle);
+        }
+    }
+
+    fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(core::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn sleep_then_complete_reclaims_its_alarm_slot() {
+        // A table with a single slot: if a completed/dropped `Sleep` ever leaked its
+        // reservation, the second iteration's `set_alarm` would find the table still full.
+        let driver = TestDriver {
+            now: Cell::new(Instant::new(0)),
+            alarms: RefCell::new(AlarmTable::new()),
+        };
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        for tick in 1..=3u32 {
+            let mut sleep = Sleep::until(&driver, Instant::new(tick));
+            assert_eq!(Pin::new(&mut sleep).poll(&mut cx), Poll::Pending);
+            assert!(driver.alarms.borrow().next_deadline().is_some());
+
+            driver.now.set(Instant::new(tick));
+            assert_eq!(Pin::new(&mut sleep).poll(&mut cx), Poll::Ready(()));
+            drop(sleep);
+            assert!(driver.alarms.borrow().next_deadline().is_none());
+        }
+    }
+
+    #[test]
+    fn dropping_a_pending_sleep_releases_its_alarm_slot() {
+        let driver = TestDriver {
+            now: Cell::new(Instant::new(0)),
+            alarms: RefCell::new(AlarmTable::new()),
+        };
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut first = Sleep::until(&driver, Instant::new(10));
+        assert_eq!(Pin::new(&mut first).poll(&mut cx), Poll::Pending);
+        drop(first);
+        assert!(driver.alarms.borrow().next_deadline().is_none());
+
+        // With the slot released, a second, unrelated `Sleep` can still be armed.
+        let mut second = Sleep::until(&driver, Instant::new(20));
+        assert_eq!(Pin::new(&mut second).poll(&mut cx), Poll::Pending);
+        assert!(driver.alarms.borrow().next_deadline().is_some());
+    }
+}
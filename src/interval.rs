@@ -0,0 +1,190 @@
+//! A periodic tick stream built on a [`Clock`]
+//!
+//! Where a [`Timer`](crate::timer::Timer) expresses a one-shot or simple periodic wait,
+//! [`Interval`] models a recurring schedule with an explicit, configurable policy for what
+//! happens when a tick is serviced late.
+
+use crate::{clock::Clock, duration::Duration, fixed_point::FixedPoint, instant::Instant};
+
+/// Policy for how an [`Interval`] behaves when one or more ticks were missed
+///
+/// A tick is "missed" when [`Interval::tick`]/[`Interval::poll_tick`] is not called again until
+/// after the next deadline has already elapsed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MissedTickBehavior {
+    /// Fire the backlog immediately, keeping the original phase
+    ///
+    /// The next deadline is simply the missed deadline plus one period, so a caller that falls
+    /// behind will observe a burst of back-to-back ticks until it catches up.
+    Burst,
+    /// Drop the backlog and reschedule relative to now
+    ///
+    /// The next deadline becomes `now + period`, discarding the original phase.
+    Delay,
+    /// Drop the backlog but keep the original phase alignment
+    ///
+    /// The next deadline becomes the first multiple of `period` (from the original schedule)
+    /// strictly after `now`, collapsing the missed ticks without drifting the phase.
+    Skip,
+}
+
+/// A stream of periodic tick [`Instant`]s, driven by a [`Clock`]
+pub struct Interval<'a, C: Clock, Dur: Duration + FixedPoint<T = C::T>> {
+    clock: &'a C,
+    period: Dur,
+    next: Instant<C>,
+    behavior: MissedTickBehavior,
+}
+
+impl<'a, C: Clock, Dur: Duration + FixedPoint<T = C::T> + Copy> Interval<'a, C, Dur> {
+    /// Construct an interval with the given `period`, due to fire once `period` from now
+    ///
+    /// Ticks are serviced per [`MissedTickBehavior::Burst`] by default; change this with
+    /// [`set_missed_tick_behavior`](Self::set_missed_tick_behavior).
+    ///
+    /// # Errors
+    ///
+    /// Propagates [`Clock::try_now`]'s error.
+    pub fn new(clock: &'a C, period: Dur) -> Result<Self, crate::clock::Error> {
+        let now = clock.try_now()?;
+        Ok(Self {
+            clock,
+            period,
+            next: now + period,
+            behavior: MissedTickBehavior::Burst,
+        })
+    }
+
+    /// Select the policy used when a tick is serviced late
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.behavior = behavior;
+    }
+
+    /// Block until the next tick is due, returning its `Instant`
+    ///
+    /// Reuses the busy-wait pattern of [`Delay`](crate::delay::Delay): `wait` is invoked in a
+    /// loop until the clock reaches the deadline.
+    ///
+    /// # Errors
+    ///
+    /// Propagates [`Clock::try_now`]'s error.
+    pub fn tick(&mut self, mut wait: impl FnMut()) -> Result<Instant<C>, crate::clock::Error> {
+        let now = loop {
+            let now = self.clock.try_now()?;
+            if now >= self.next {
+                break now;
+            }
+            wait();
+        };
+        let fired = self.next;
+        self.advance(now);
+        Ok(fired)
+    }
+
+    /// Non-blocking poll for whether the next tick is due
+    ///
+    /// Returns `Some(Instant)` the first time this is called after the deadline has passed,
+    /// advancing the schedule according to the configured [`MissedTickBehavior`]; returns `None`
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Propagates [`Clock::try_now`]'s error.
+    pub fn poll_tick(&mut self) -> Result<Option<Instant<C>>, crate::clock::Error> {
+        let now = self.clock.try_now()?;
+        if now < self.next {
+            return Ok(None);
+        }
+        let fired = self.next;
+        self.advance(now);
+        Ok(Some(fired))
+    }
+
+    fn advance(&mut self, now: Instant<C>) {
+        self.next = match self.behavior {
+            MissedTickBehavior::Burst => self.next + self.period,
+            MissedTickBehavior::Delay => now + self.period,
+            MissedTickBehavior::Skip => {
+                // `now` is at or past `self.next`, so find the smallest whole number of
+                // periods that, added to the missed deadline, lands strictly after `now` -
+                // directly via div/mod rather than by walking one period at a time, so a long
+                // backlog (e.g. after a deep-sleep wakeup) costs one division, not one
+                // iteration per missed period.
+                let elapsed: Dur = now - self.next;
+                let period_ticks = *self.period.integer();
+                let periods_missed = *elapsed.integer() / period_ticks;
+                self.next + Dur::new(period_ticks * (periods_missed + 1.into()))
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock, clock::ClockDuration, fraction::Fraction};
+    use core::cell::Cell;
+
+    struct TestClock {
+        now: Cell<u32>,
+    }
+
+    impl Clock for TestClock {
+        type T = u32;
+        const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000);
+
+        fn try_now(&self) -> Result<Instant<Self>, clock::Error> {
+            Ok(Instant::new(self.now.get()))
+        }
+    }
+
+    #[test]
+    fn burst_fires_the_backlog_one_tick_per_poll_without_skipping_ahead() {
+        let clock = TestClock { now: Cell::new(0) };
+        let period = ClockDuration::<TestClock>::new(10);
+        let mut interval = Interval::new(&clock, period).unwrap();
+        interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+        // Two periods' worth of backlog (deadlines 10 and 20) have already elapsed.
+        clock.now.set(25);
+        assert_eq!(interval.poll_tick().unwrap(), Some(Instant::new(10)));
+        // Burst keeps the original phase, so the backlog drains one tick per poll instead of
+        // collapsing to the present.
+        assert_eq!(interval.poll_tick().unwrap(), Some(Instant::new(20)));
+        assert_eq!(interval.poll_tick().unwrap(), None);
+    }
+
+    #[test]
+    fn delay_reschedules_relative_to_now_and_drops_the_backlog() {
+        let clock = TestClock { now: Cell::new(0) };
+        let period = ClockDuration::<TestClock>::new(10);
+        let mut interval = Interval::new(&clock, period).unwrap();
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        clock.now.set(25);
+        assert_eq!(interval.poll_tick().unwrap(), Some(Instant::new(10)));
+        // Delay discards the backlog: the next deadline is `now + period` (35), not
+        // `missed_deadline + period` (20), so there's nothing left to fire yet.
+        assert_eq!(interval.poll_tick().unwrap(), None);
+        clock.now.set(35);
+        assert_eq!(interval.poll_tick().unwrap(), Some(Instant::new(35)));
+    }
+
+    #[test]
+    fn skip_collapses_a_large_backlog_via_div_mod_to_the_next_aligned_deadline() {
+        let clock = TestClock { now: Cell::new(0) };
+        let period = ClockDuration::<TestClock>::new(10);
+        let mut interval = Interval::new(&clock, period).unwrap();
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        // Deadlines 10, 20, 30, 40 have all elapsed by the time `now` reaches 47 - div/mod
+        // must collapse all four in one call rather than looping once per missed period.
+        clock.now.set(47);
+        assert_eq!(interval.poll_tick().unwrap(), Some(Instant::new(10)));
+        // Skip keeps the original phase alignment: the next deadline is 50, the first
+        // multiple of the schedule strictly after 47, not 47 + period.
+        assert_eq!(interval.poll_tick().unwrap(), None);
+        clock.now.set(50);
+        assert_eq!(interval.poll_tick().unwrap(), Some(Instant::new(50)));
+    }
+}
@@ -0,0 +1,21 @@
+//! The error type returned by [`Clock::with_timeout`](crate::clock::Clock::with_timeout)
+
+use core::fmt::{self, Formatter};
+
+/// Error returned by [`Clock::with_timeout`](crate::clock::Clock::with_timeout)
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub enum TimeoutError<E> {
+    /// The duration elapsed before the operation reported completion
+    TimedOut,
+    /// The operation itself reported an error
+    Other(E),
+}
+
+impl<E: fmt::Display> fmt::Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TimedOut => write!(f, "operation timed out"),
+            Self::Other(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
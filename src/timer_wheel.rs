@@ -0,0 +1,357 @@
+//! A hierarchical timing wheel for scheduling many software deadlines behind a single [`Clock`]
+//!
+//! Where a [`Timer`](crate::timer::Timer) tracks a single deadline, [`TimerWheel`] is a
+//! fixed-capacity registry of many deadlines driven by one `Clock`. Insertion, cancellation, and
+//! "time until the next expiry" are all amortized O(1), which makes it suitable for driving
+//! thousands of software timers off of a single hardware tick source instead of spawning a
+//! `Timer` per wait.
+
+use crate::clock::Clock;
+
+/// Number of levels in the wheel
+const LEVELS: usize = 6;
+/// Number of slots per level
+const SLOTS: usize = 64;
+/// `log2(SLOTS)`, i.e. how many bits of the deadline each level accounts for
+const SHIFT: u32 = 6;
+const SLOT_MASK: u64 = (SLOTS - 1) as u64;
+
+/// A handle to a deadline previously inserted into a [`TimerWheel`]
+///
+/// Used to [`cancel`](TimerWheel::cancel) the deadline before it fires.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Handle(usize);
+
+#[derive(Clone, Copy)]
+struct Entry<Tick, T> {
+    deadline: Tick,
+    payload: T,
+    next: Option<usize>,
+}
+
+/// A fixed-capacity hierarchical timing wheel of up to `CAP` deadlines, driven by a [`Clock`]
+///
+/// Deadlines are bucketed by `elapsed = deadline - now`: a deadline lives in the lowest level
+/// `L` for which `elapsed >> (6 * L)` fits a single slot, re-cascading into lower levels as
+/// `now` catches up. A 64-bit occupancy bitmap per level lets the next non-empty slot be found
+/// without scanning empty ones; within a level, the bitmap is rotated so the slot the wheel is
+/// currently passing through lands at bit 0 before searching, so `trailing_zeros` returns the
+/// *nearest* occupied slot rather than the lowest-numbered one. [`poll`](Self::poll) remembers
+/// the position of the last call and only revisits the slots actually passed through since
+/// then, so cost is proportional to elapsed wheel movement, not to the number of registered
+/// deadlines.
+pub struct TimerWheel<C: Clock, T: Copy, const CAP: usize> {
+    entries: [Option<Entry<C::T, T>>; CAP],
+    slots: [[Option<usize>; SLOTS]; LEVELS],
+    occupied: [u64; LEVELS],
+    free: [usize; CAP],
+    free_len: usize,
+    /// `now` as of the last call to [`poll`](Self::poll), used to bound how much of the wheel
+    /// that call needs to revisit
+    position: C::T,
+}
+
+impl<C: Clock, T: Copy, const CAP: usize> TimerWheel<C, T, CAP>
+where
+    C::T: Into<u64>,
+{
+    /// Construct an empty wheel
+    pub fn new() -> Self {
+        let mut free = [0; CAP];
+        for (i, slot) in free.iter_mut().enumerate() {
+            *slot = CAP - 1 - i;
+        }
+        Self {
+            entries: [None; CAP],
+            slots: [[None; SLOTS]; LEVELS],
+            occupied: [0; LEVELS],
+            free,
+            free_len: CAP,
+            position: 0.into(),
+        }
+    }
+
+    /// Number of deadlines currently registered
+    pub fn len(&self) -> usize {
+        CAP - self.free_len
+    }
+
+    /// Whether the wheel holds no deadlines
+    pub fn is_empty(&self) -> bool {
+        self.free_len == CAP
+    }
+
+    /// Register a new `deadline` (in `Clock::T` ticks) carrying `payload`
+    ///
+    /// Returns `None`, leaving the wheel unchanged, if it is already at capacity.
+    pub fn insert(&mut self, now: C::T, deadline: C::T, payload: T) -> Option<Handle> {
+        if self.free_len == 0 {
+            return None;
+        }
+        self.free_len -= 1;
+        let index = self.free[self.free_len];
+        let (level, slot) = Self::level_slot(now, deadline);
+        let next = self.slots[level][slot];
+        self.entries[index] = Some(Entry {
+            deadline,
+            payload,
+            next,
+        });
+        self.slots[level][slot] = Some(index);
+        self.occupied[level] |= 1 << slot;
+        Some(Handle(index))
+    }
+
+    /// Remove a previously inserted deadline before it fires, returning its payload
+    ///
+    /// Returns `None` if `handle` has already fired or been cancelled.
+    pub fn cancel(&mut self, now: C::T, handle: Handle) -> Option<T> {
+        // `unlink` walks the slot's chain through `entries`, so the entry stays in place until
+        // it has been unlinked.
+        let entry = self.entries[handle.0]?;
+        let (level, slot) = Self::level_slot(now, entry.deadline);
+        // The entry's level was chosen relative to the `now` it was last placed at (insertion
+        // or a cascading `poll`), which need not be this `now`: fall back to the other levels.
+        if !self.unlink(level, slot, handle.0) {
+            for other in (0..LEVELS).filter(|&other| other != level) {
+                if self.unlink(other, Self::current_slot(other, entry.deadline), handle.0) {
+                    break;
+                }
+            }
+        }
+        self.entries[handle.0] = None;
+        self.free[self.free_len] = handle.0;
+        self.free_len += 1;
+        Some(entry.payload)
+    }
+
+    /// Remove `index` from the chain of `(level, slot)`, returning whether it was found there
+    fn unlink(&mut self, level: usize, slot: usize, index: usize) -> bool {
+        let mut cur = self.slots[level][slot];
+        let mut prev = None;
+        while let Some(i) = cur {
+            let next = self.entries[i].unwrap().next;
+            if i == index {
+                match prev {
+                    Some(p) => self.entries[p].as_mut().unwrap().next = next,
+                    None => self.slots[level][slot] = next,
+                }
+                if self.slots[level][slot].is_none() {
+                    self.occupied[level] &= !(1 << slot);
+                }
+                return true;
+            }
+            prev = cur;
+            cur = next;
+        }
+        false
+    }
+
+    /// Ticks (in `Clock::T`) until the next deadline expires, or `None` if the wheel is empty
+    ///
+    /// O(1): callers can use this to program a low-power sleep on the underlying `Clock`
+    /// instead of polling it.
+    pub fn ticks_until_next(&self, now: C::T) -> Option<C::T> {
+        // Each entry's level was chosen relative to the `now` it was last placed at, so a lower
+        // level does not always hold the nearer deadline: take the minimum across all levels.
+        let mut nearest: Option<C::T> = None;
+        for level in 0..LEVELS {
+            if self.occupied[level] == 0 {
+                continue;
+            }
+            // The slot a deadline's bits hash to has no relation to how soon it fires relative
+            // to `now`: rotate the bitmap so the slot the wheel is currently passing through at
+            // this level sits at bit 0, so `trailing_zeros` walks forward from *there* instead
+            // of from absolute slot 0.
+            let cursor = Self::current_slot(level, now);
+            let rotated = self.occupied[level].rotate_right(cursor as u32);
+            let distance = rotated.trailing_zeros() as usize;
+            let slot = (cursor + distance) % SLOTS;
+            let mut cur = self.slots[level][slot];
+            while let Some(index) = cur {
+                let entry = self.entries[index].unwrap();
+                nearest = Some(match nearest {
+                    Some(min) if min <= entry.deadline => min,
+                    _ => entry.deadline,
+                });
+                cur = entry.next;
+            }
+        }
+        nearest.map(|deadline| if deadline > now { deadline - now } else { now - now })
+    }
+
+    /// Advance the wheel to `now`, writing expired payloads into `expired` and returning how
+    /// many deadlines fired
+    ///
+    /// Entries in elapsed slots whose deadline is still in the future relative to `now` are
+    /// cascaded down into the level appropriate for their remaining time rather than reported
+    /// as expired. If more deadlines fire than `expired` can hold, the excess are still removed
+    /// from the wheel but not reported; size `expired` to `CAP` to avoid this.
+    ///
+    /// Only the slots the wheel has actually passed through since the previous call are
+    /// inspected, so cost is proportional to elapsed wheel movement (capped by a handful of
+    /// occupied slots per level), not to the number of deadlines currently registered.
+    pub fn poll(&mut self, now: C::T, expired: &mut [T]) -> usize {
+        let prev = self.position;
+        self.position = now;
+        if now <= prev {
+            return 0;
+        }
+        let now_u64: u64 = now.into();
+        let prev_u64: u64 = prev.into();
+        let mut count = 0;
+        for level in 0..LEVELS {
+            if self.occupied[level] == 0 {
+                continue;
+            }
+            let shift = SHIFT * level as u32;
+            // The number of slot-index transitions at this level is `floor(now/m) -
+            // floor(prev/m)`, not `floor((now-prev)/m)` - the two differ by one whenever
+            // `prev`'s low bits sit close to a boundary, which would otherwise leave the slot
+            // `now` just crossed into unvisited.
+            let level_elapsed = (now_u64 >> shift) - (prev_u64 >> shift);
+            if level_elapsed >= SLOTS as u64 {
+                // A full revolution (or more) of this level has passed since the last poll:
+                // every occupied slot is now due for re-evaluation. Still only visit the ones
+                // that actually hold something, via the occupancy bitmap.
+                let mut bitmap = self.occupied[level];
+                while bitmap != 0 {
+                    let slot = bitmap.trailing_zeros() as usize;
+                    bitmap &= !(1 << slot);
+                    count += self.drain_slot(level, slot, now, expired, count);
+                }
+            } else {
+                // Otherwise only the slots between the wheel's previous position and now, at
+                // this level's granularity, could possibly have anything due.
+                let prev_slot = Self::current_slot(level, prev);
+                for step in 1..=level_elapsed {
+                    let slot = (prev_slot + step as usize) % SLOTS;
+                    if self.occupied[level] & (1 << slot) != 0 {
+                        count += self.drain_slot(level, slot, now, expired, count);
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Detach every entry in `(level, slot)`, firing those at or before `now` into `expired`
+    /// (starting at `expired[already..]`) and cascading the rest
+    fn drain_slot(
+        &mut self,
+        level: usize,
+        slot: usize,
+        now: C::T,
+        expired: &mut [T],
+        already: usize,
+    ) -> usize {
+        let mut cur = self.slots[level][slot].take();
+        self.occupied[level] &= !(1 << slot);
+        let mut count = 0;
+        while let Some(index) = cur {
+            let entry = self.entries[index].unwrap();
+            cur = entry.next;
+            if entry.deadline <= now {
+                if let Some(out) = expired.get_mut(already + count) {
+                    *out = entry.payload;
+                }
+                count += 1;
+                self.entries[index] = None;
+                self.free[self.free_len] = index;
+                self.free_len += 1;
+            } else {
+                let (new_level, new_slot) = Self::level_slot(now, entry.deadline);
+                let next = self.slots[new_level][new_slot];
+                self.entries[index] = Some(Entry { next, ..entry });
+                self.slots[new_level][new_slot] = Some(index);
+                self.occupied[new_level] |= 1 << new_slot;
+            }
+        }
+        count
+    }
+
+    /// The (level, slot) a deadline currently belongs in, relative to `now`
+    fn level_slot(now: C::T, deadline: C::T) -> (usize, usize) {
+        let elapsed: u64 = if deadline > now {
+            (deadline - now).into()
+        } else {
+            0
+        };
+        let mut level = 0;
+        while level + 1 < LEVELS && (elapsed >> (SHIFT * (level as u32 + 1))) != 0 {
+            level += 1;
+        }
+        (level, Self::current_slot(level, deadline))
+    }
+
+    /// The slot within `level` that `tick` currently hashes to
+    fn current_slot(level: usize, tick: C::T) -> usize {
+        ((tick.into() >> (SHIFT * level as u32)) & SLOT_MASK) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock, fraction::Fraction, instant::Instant};
+
+    struct TestClock;
+
+    impl Clock for TestClock {
+        type T = u32;
+        const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000);
+
+        fn try_now(&self) -> Result<Instant<Self>, clock::Error> {
+            Err(clock::Error::NotRunning)
+        }
+    }
+
+    #[test]
+    fn ticks_until_next_picks_the_absolute_nearest_deadline_within_a_level() {
+        let mut wheel: TimerWheel<TestClock, u32, 4> = TimerWheel::new();
+        // Both land in level 0, but slot(130) = 2 < slot(101) = 37, so a naive
+        // lowest-slot-number search would (wrongly) report the 130 deadline as nearer.
+        wheel.insert(100, 130, 1).unwrap();
+        wheel.insert(100, 101, 2).unwrap();
+        assert_eq!(wheel.ticks_until_next(100), Some(1));
+    }
+
+    #[test]
+    fn poll_only_touches_entries_that_could_have_expired() {
+        let mut wheel: TimerWheel<TestClock, u32, 4> = TimerWheel::new();
+        wheel.insert(0, 5, 1).unwrap();
+        wheel.insert(0, 1_000, 2).unwrap();
+        let mut expired = [0u32; 4];
+        let count = wheel.poll(5, &mut expired);
+        assert_eq!(count, 1);
+        assert_eq!(expired[0], 1);
+        assert_eq!(wheel.len(), 1);
+    }
+
+    #[test]
+    fn poll_cascades_entries_that_have_not_yet_expired() {
+        let mut wheel: TimerWheel<TestClock, u32, 4> = TimerWheel::new();
+        wheel.insert(0, 1_000, 1).unwrap();
+        let mut expired = [0u32; 4];
+        // 1_000 starts in level 1, slot 15. Polling to 960 passes that slot while the deadline
+        // is still 40 ticks away, so the entry is cascaded into level 0 instead of firing.
+        assert_eq!(wheel.poll(960, &mut expired), 0);
+        assert_eq!(wheel.len(), 1);
+        assert_eq!(wheel.poll(1_000, &mut expired), 1);
+        assert_eq!(expired[0], 1);
+    }
+
+    #[test]
+    fn poll_fires_a_level_1_deadline_when_prevs_low_bits_sit_just_before_the_boundary() {
+        // `deadline = 113` lands in level 1, slot 1. Polling to 50 first (slot 0 at level 1)
+        // means the level-1 boundary between slot 0 and slot 1 is crossed between the two
+        // `poll` calls below, even though `now - prev` (63) shifted right by 6 floors to 0.
+        let mut wheel: TimerWheel<TestClock, u32, 4> = TimerWheel::new();
+        wheel.insert(0, 113, 1).unwrap();
+        let mut expired = [0u32; 4];
+        assert_eq!(wheel.poll(50, &mut expired), 0);
+        assert_eq!(wheel.len(), 1);
+        assert_eq!(wheel.poll(113, &mut expired), 1);
+        assert_eq!(expired[0], 1);
+    }
+}